@@ -0,0 +1,141 @@
+//! Command-line surface of the `prod_test_driver` binary.
+//!
+//! [`CliArgs`] is the raw `structopt` form; [`CliArgs::validate`] compiles the
+//! filter patterns and normalizes everything into [`ValidatedCliArgs`], which
+//! the rest of the driver consumes.
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use regex::Regex;
+use structopt::StructOpt;
+
+/// How the result file is encoded on disk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResultCompression {
+    /// Plain, uncompressed JSON.
+    None,
+    /// A single-stream zstd-compressed JSON `.zst` file.
+    Zstd,
+}
+
+impl FromStr for ResultCompression {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "none" => Ok(ResultCompression::None),
+            "zstd" => Ok(ResultCompression::Zstd),
+            other => bail!("unknown result compression {:?}, expected none or zstd", other),
+        }
+    }
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "prod-test-driver", about = "Production test driver.")]
+pub struct CliArgs {
+    /// Name of the suite to run.
+    #[structopt(long)]
+    pub suite: String,
+
+    /// Path the result JSON is written to.
+    #[structopt(long, parse(from_os_str))]
+    pub result_file: Option<PathBuf>,
+
+    /// Only run tests whose `suite::pot::test` path matches this regex.
+    #[structopt(long)]
+    pub include_pattern: Option<String>,
+
+    /// Ignore tests whose path matches this regex.
+    #[structopt(long)]
+    pub ignore_pattern: Option<String>,
+
+    /// Skip (but still report) tests whose path matches this regex.
+    #[structopt(long)]
+    pub skip_pattern: Option<String>,
+
+    /// Render a live progress bar per test while the suite runs.
+    #[structopt(long)]
+    pub progress: bool,
+
+    /// How the result file is encoded: `none` (default) or `zstd`.
+    #[structopt(long, default_value = "none")]
+    pub result_compression: ResultCompression,
+
+    /// Deterministically shuffle the qualified tests within each pot using this
+    /// seed (`Sequence` pots only with `--shuffle-sequences`). The seed is
+    /// echoed into the result JSON for reproduction.
+    #[structopt(long)]
+    pub shuffle_seed: Option<u64>,
+
+    /// Also shuffle `Sequence` pots (off by default, since some sequences are
+    /// intentionally ordered). Only meaningful with `--shuffle-seed`.
+    #[structopt(long)]
+    pub shuffle_sequences: bool,
+
+    /// Also shuffle the order of the pots themselves. Only meaningful with
+    /// `--shuffle-seed`.
+    #[structopt(long)]
+    pub shuffle_pots: bool,
+
+    /// Load a prior result file and skip tests that previously succeeded,
+    /// carrying their outcomes over into the merged result.
+    #[structopt(long, parse(from_os_str))]
+    pub resume_from: Option<PathBuf>,
+
+    /// Ignore `--resume-from` skipping and re-run every qualified test.
+    #[structopt(long)]
+    pub force: bool,
+
+    /// Override the per-run iteration budget for every fuzz pot.
+    #[structopt(long)]
+    pub fuzz_iterations: Option<usize>,
+
+    /// Override the corpus directory for every fuzz pot.
+    #[structopt(long, parse(from_os_str))]
+    pub fuzz_corpus_dir: Option<PathBuf>,
+}
+
+/// The validated, normalized form of [`CliArgs`].
+pub struct ValidatedCliArgs {
+    pub suite: String,
+    pub result_file: Option<PathBuf>,
+    pub include_pattern: Option<Regex>,
+    pub ignore_pattern: Option<Regex>,
+    pub skip_pattern: Option<Regex>,
+    pub progress: bool,
+    pub result_compression: ResultCompression,
+    pub shuffle_seed: Option<u64>,
+    pub shuffle_sequences: bool,
+    pub shuffle_pots: bool,
+    pub resume_from: Option<PathBuf>,
+    pub force: bool,
+    pub fuzz_iterations: Option<usize>,
+    pub fuzz_corpus_dir: Option<PathBuf>,
+}
+
+impl CliArgs {
+    /// Compile the filter patterns and produce the validated arguments.
+    pub fn validate(self) -> Result<ValidatedCliArgs> {
+        let compile = |p: Option<String>| -> Result<Option<Regex>> {
+            p.map(|p| Regex::new(&p)).transpose().map_err(Into::into)
+        };
+        Ok(ValidatedCliArgs {
+            suite: self.suite,
+            result_file: self.result_file,
+            include_pattern: compile(self.include_pattern)?,
+            ignore_pattern: compile(self.ignore_pattern)?,
+            skip_pattern: compile(self.skip_pattern)?,
+            progress: self.progress,
+            result_compression: self.result_compression,
+            shuffle_seed: self.shuffle_seed,
+            shuffle_sequences: self.shuffle_sequences,
+            shuffle_pots: self.shuffle_pots,
+            resume_from: self.resume_from,
+            force: self.force,
+            fuzz_iterations: self.fuzz_iterations,
+            fuzz_corpus_dir: self.fuzz_corpus_dir,
+        })
+    }
+}
@@ -0,0 +1,338 @@
+//! Drives a [`Suite`] against a provisioned driver context and collects the
+//! outcome into a [`TestResult`] tree.
+//!
+//! When a lifecycle `Sender` is supplied, `evaluate` emits a
+//! [`TestLifecycleEvent`] as each test transitions through started →
+//! passed/failed so the binary's progress reporter can render it live. The
+//! shared `stop` flag lets the caller cancel between tests and pots.
+
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::prod_tests::driver_setup::DriverContext;
+use crate::prod_tests::pot_dsl::{ExecutionMode, FuzzConfig, Suite, Test, TestSet};
+
+/// The lifecycle transition a test just made.
+#[derive(Clone, Copy, Debug)]
+pub enum TestLifecycle {
+    Started,
+    Passed,
+    Failed,
+}
+
+/// A single lifecycle transition, tagged with the owning pot and test so the
+/// reporter can find the right bar.
+#[derive(Clone, Debug)]
+pub struct TestLifecycleEvent {
+    pub pot: String,
+    pub test: String,
+    pub lifecycle: TestLifecycle,
+}
+
+/// The outcome of a suite, pot, or test. Leaf nodes (`children` empty) are
+/// individual tests; inner nodes aggregate their children's success.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TestResult {
+    pub name: String,
+    pub succeeded: bool,
+    /// The shuffle seed this run used, recorded at the suite root so a failing
+    /// randomized run can be reproduced bit-for-bit. `None` on pot/test nodes
+    /// and when no seed was given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shuffle_seed: Option<u64>,
+    /// Set when the test was scheduled but never ran to completion because the
+    /// suite was interrupted (SIGINT/SIGTERM).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub interrupted: bool,
+    /// For a fuzz test, the path of the persisted reproducer when an input
+    /// crashed the target; `None` otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crash: Option<PathBuf>,
+    #[serde(default)]
+    pub children: Vec<TestResult>,
+}
+
+impl TestResult {
+    fn leaf(name: &str, succeeded: bool) -> Self {
+        TestResult {
+            name: name.to_string(),
+            succeeded,
+            shuffle_seed: None,
+            interrupted: false,
+            crash: None,
+            children: Vec::new(),
+        }
+    }
+
+    fn interrupted(name: &str) -> Self {
+        TestResult {
+            name: name.to_string(),
+            succeeded: false,
+            shuffle_seed: None,
+            interrupted: true,
+            crash: None,
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Run a single test closure, treating a panic as a failure, and emit the
+/// matching lifecycle events.
+fn run_test(
+    context: &DriverContext,
+    pot: &str,
+    test: &Test,
+    events: &Option<Sender<TestLifecycleEvent>>,
+) -> TestResult {
+    emit(events, pot, &test.name, TestLifecycle::Started);
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| (test.f)(context.clone())));
+    let succeeded = outcome.is_ok();
+    emit(
+        events,
+        pot,
+        &test.name,
+        if succeeded {
+            TestLifecycle::Passed
+        } else {
+            TestLifecycle::Failed
+        },
+    );
+    TestResult::leaf(&test.name, succeeded)
+}
+
+/// Build a pot result for a pot that never ran because the suite was
+/// interrupted, marking each of its qualified tests as interrupted.
+fn interrupted_pot(pot: &crate::prod_tests::pot_dsl::Pot) -> TestResult {
+    let tests = match &pot.testset {
+        TestSet::Parallel(tests) | TestSet::Sequence(tests) | TestSet::Fuzz(tests, _) => tests,
+    };
+    let children = tests
+        .iter()
+        .filter(|t| t.execution_mode == ExecutionMode::Run)
+        .map(|t| TestResult::interrupted(&t.name))
+        .collect();
+    TestResult {
+        name: pot.name.clone(),
+        succeeded: false,
+        shuffle_seed: None,
+        interrupted: true,
+        crash: None,
+        children,
+    }
+}
+
+fn emit(events: &Option<Sender<TestLifecycleEvent>>, pot: &str, test: &str, lifecycle: TestLifecycle) {
+    if let Some(tx) = events {
+        let _ = tx.send(TestLifecycleEvent {
+            pot: pot.to_string(),
+            test: test.to_string(),
+            lifecycle,
+        });
+    }
+}
+
+/// Evaluate `suite` against `context`.
+///
+/// `events`, when present, receives a [`TestLifecycleEvent`] per transition.
+/// `stop` is checked between tests and pots; once set, no further pots are
+/// scheduled and the (partial) result is returned so the caller can still
+/// persist it.
+pub fn evaluate(
+    context: &DriverContext,
+    suite: Suite,
+    events: Option<Sender<TestLifecycleEvent>>,
+    stop: Arc<AtomicBool>,
+) -> TestResult {
+    let mut pot_results = Vec::new();
+    let mut interrupted = false;
+    for pot in &suite.pots {
+        if pot.execution_mode != ExecutionMode::Run {
+            continue;
+        }
+        // Stop scheduling new pots once cancellation is requested, but keep
+        // walking so every remaining qualified test is recorded as interrupted
+        // rather than silently dropped.
+        if interrupted || stop.load(Ordering::SeqCst) {
+            interrupted = true;
+            pot_results.push(interrupted_pot(pot));
+            continue;
+        }
+        let (tests, fuzz) = match &pot.testset {
+            TestSet::Parallel(tests) | TestSet::Sequence(tests) => (tests, None),
+            TestSet::Fuzz(tests, config) => (tests, Some(config)),
+        };
+        let mut test_results = Vec::new();
+        for test in tests {
+            if test.execution_mode != ExecutionMode::Run {
+                continue;
+            }
+            if interrupted || stop.load(Ordering::SeqCst) {
+                interrupted = true;
+                test_results.push(TestResult::interrupted(&test.name));
+                continue;
+            }
+            let result = match fuzz {
+                Some(config) => run_fuzz(context, &pot.name, test, config, &events, &stop),
+                None => run_test(context, &pot.name, test, &events),
+            };
+            interrupted |= result.interrupted;
+            test_results.push(result);
+        }
+        let succeeded = test_results.iter().all(|r| r.succeeded);
+        pot_results.push(TestResult {
+            name: pot.name.clone(),
+            succeeded,
+            shuffle_seed: None,
+            interrupted: false,
+            crash: None,
+            children: test_results,
+        });
+    }
+    // Release the provisioned VMs/nodes before returning the (possibly partial)
+    // result. On a clean finish this is a no-op beyond the usual teardown; on an
+    // interrupt it guarantees the driver context is not leaked.
+    if interrupted {
+        context.tear_down();
+    }
+    let succeeded = !interrupted && pot_results.iter().all(|r| r.succeeded);
+    TestResult {
+        name: suite.name,
+        succeeded,
+        shuffle_seed: None,
+        interrupted,
+        crash: None,
+        children: pot_results,
+    }
+}
+
+thread_local! {
+    /// The fuzz input for the test running on this thread, set by `run_fuzz`
+    /// before each invocation and read by the target via [`fuzz_input`].
+    static FUZZ_INPUT: std::cell::RefCell<Vec<u8>> = std::cell::RefCell::new(Vec::new());
+}
+
+/// The current fuzz input, as set by the fuzz driver. Fuzz targets call this to
+/// obtain the payload the harness generated or replayed for this iteration.
+pub fn fuzz_input() -> Vec<u8> {
+    FUZZ_INPUT.with(|i| i.borrow().clone())
+}
+
+fn set_fuzz_input(input: &[u8]) {
+    FUZZ_INPUT.with(|i| *i.borrow_mut() = input.to_vec());
+}
+
+/// Drive a single fuzz target honggfuzz-style: replay the persisted corpus
+/// first (so known crashers resurface immediately), then fill the remaining
+/// budget with freshly generated or mutated-corpus inputs. The input for each
+/// iteration is published via [`fuzz_input`] so the target can read it.
+///
+/// Only crashing inputs are kept in the corpus; transient inputs are written to
+/// a scratch workspace outside it, so the corpus stays the set of interesting
+/// reproducers rather than every input ever tried.
+fn run_fuzz(
+    context: &DriverContext,
+    pot: &str,
+    test: &Test,
+    config: &FuzzConfig,
+    events: &Option<Sender<TestLifecycleEvent>>,
+    stop: &Arc<AtomicBool>,
+) -> TestResult {
+    emit(events, pot, &test.name, TestLifecycle::Started);
+    let _ = fs::create_dir_all(&config.corpus_dir);
+    let scratch = scratch_dir(config, &test.name);
+    let _ = fs::create_dir_all(&scratch);
+
+    // A deterministic RNG keyed off the test name keeps a run reproducible
+    // while still exercising a different input stream per target.
+    let mut rng = StdRng::seed_from_u64(name_seed(&test.name));
+
+    // The corpus holds only previously-persisted interesting inputs (crashers);
+    // replay them verbatim first, then mutate them or generate fresh inputs.
+    let corpus: Vec<Vec<u8>> = fs::read_dir(&config.corpus_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| fs::read(e.path()).ok())
+        .collect();
+
+    for i in 0..config.iterations {
+        if stop.load(Ordering::SeqCst) {
+            return TestResult::interrupted(&test.name);
+        }
+        let input = if i < corpus.len() {
+            // Replay a known interesting input verbatim.
+            corpus[i].clone()
+        } else if !corpus.is_empty() && rng.next_u32() % 2 == 0 {
+            // Mutate a corpus entry.
+            mutate(&corpus[(rng.next_u32() as usize) % corpus.len()], &mut rng)
+        } else {
+            // Generate a fresh input.
+            (config.generator)(&mut rng)
+        };
+
+        // Hand the input to the target and keep a transient copy in the scratch
+        // workspace for post-mortem, outside the persistent corpus.
+        let _ = fs::write(scratch.join(format!("input-{:06}", i)), &input);
+        set_fuzz_input(&input);
+
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| (test.f)(context.clone())));
+        if outcome.is_err() {
+            // Persist the crashing input into the corpus so a later run replays
+            // it first, and record the reproducer path in the result.
+            let crash_path = config.corpus_dir.join(format!("crash-{:06}", i));
+            let _ = fs::write(&crash_path, &input);
+            emit(events, pot, &test.name, TestLifecycle::Failed);
+            return TestResult {
+                name: test.name.clone(),
+                succeeded: false,
+                shuffle_seed: None,
+                interrupted: false,
+                crash: Some(crash_path),
+                children: Vec::new(),
+            };
+        }
+    }
+
+    emit(events, pot, &test.name, TestLifecycle::Passed);
+    TestResult::leaf(&test.name, true)
+}
+
+/// The transient scratch workspace for a fuzz target, a sibling of the corpus
+/// dir so throwaway inputs never pollute the corpus itself.
+fn scratch_dir(config: &FuzzConfig, test: &str) -> PathBuf {
+    let stem = config
+        .corpus_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "corpus".to_string());
+    config
+        .corpus_dir
+        .with_file_name(format!("{}.scratch", stem))
+        .join(test)
+}
+
+/// Produce a mutated copy of a corpus entry by flipping a random byte.
+fn mutate(seed: &[u8], rng: &mut StdRng) -> Vec<u8> {
+    let mut out = seed.to_vec();
+    if out.is_empty() {
+        out.push((rng.next_u32() & 0xff) as u8);
+    } else {
+        let idx = (rng.next_u32() as usize) % out.len();
+        out[idx] ^= (rng.next_u32() & 0xff) as u8;
+    }
+    out
+}
+
+/// A stable seed derived from a test name, so fuzz runs are reproducible.
+fn name_seed(name: &str) -> u64 {
+    name.bytes()
+        .fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3))
+}
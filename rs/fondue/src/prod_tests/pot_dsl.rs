@@ -0,0 +1,151 @@
+//! A small DSL for describing prod-test suites.
+//!
+//! A [`Suite`] owns a number of [`Pot`]s; each pot provisions an Internet
+//! Computer (via its [`PotConfig`]) and then runs a [`TestSet`] against it,
+//! in parallel, as an ordered sequence, or as a fuzz target. The
+//! `prod_test_driver` binary builds a suite with the
+//! `suite`/`pot`/`par`/`seq`/`fuzz`/`t` constructors and hands
+//! it to [`crate::prod_tests::evaluation::evaluate`].
+
+use std::fmt;
+use std::path::PathBuf;
+
+use rand::rngs::StdRng;
+
+use crate::prod_tests::driver_setup::DriverContext;
+
+/// How a pot or test should be treated for a given run, as resolved from the
+/// include/ignore/skip filters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ExecutionMode {
+    /// Provision and run the test.
+    Run,
+    /// Do not run, but still surface the test in the summary.
+    Skip,
+    /// Drop the test entirely; do not provision on its behalf.
+    Ignore,
+}
+
+/// The closure implementing a single test. It receives a handle to the
+/// provisioned driver context the pot set up.
+pub type TestFn = Box<dyn Fn(DriverContext) + Send + Sync>;
+
+/// The per-pot environment setup, produced by a test module's `config()`. It
+/// describes the IC topology the pot's tests run against.
+pub struct PotConfig(pub Box<dyn Fn(DriverContext) + Send + Sync>);
+
+/// A single named test within a pot.
+pub struct Test {
+    pub name: String,
+    pub execution_mode: ExecutionMode,
+    pub f: TestFn,
+}
+
+/// Produces a fresh fuzz input from the driver's seeded RNG. Returns the raw
+/// bytes fed to the fuzz test; corpus replay and mutation are done by the
+/// driver, not by the generator.
+pub type FuzzInputGen = fn(&mut StdRng) -> Vec<u8>;
+
+/// Configuration for a fuzzing pot: how inputs are generated, how many
+/// iterations to run, and where the persistent corpus lives.
+pub struct FuzzConfig {
+    pub generator: FuzzInputGen,
+    pub iterations: usize,
+    pub corpus_dir: PathBuf,
+}
+
+/// The set of tests a pot runs and how they are scheduled.
+pub enum TestSet {
+    /// Tests that may run concurrently.
+    Parallel(Vec<Test>),
+    /// Tests that must run in declaration order.
+    Sequence(Vec<Test>),
+    /// A property/fuzz test driven repeatedly against generated inputs, backed
+    /// by a persistent corpus.
+    Fuzz(Vec<Test>, FuzzConfig),
+}
+
+/// A pot: an IC topology plus the tests that exercise it.
+pub struct Pot {
+    pub name: String,
+    pub execution_mode: ExecutionMode,
+    pub config: PotConfig,
+    pub testset: TestSet,
+}
+
+/// A named collection of pots.
+pub struct Suite {
+    pub name: String,
+    pub pots: Vec<Pot>,
+}
+
+/// A `suite/pot/test` path, used to match tests against the filter regexes and
+/// to key results.
+#[derive(Clone, Default)]
+pub struct TestPath(Vec<String>);
+
+impl TestPath {
+    pub fn new() -> Self {
+        TestPath(Vec::new())
+    }
+
+    pub fn join<S: Into<String>>(&self, segment: S) -> Self {
+        let mut segments = self.0.clone();
+        segments.push(segment.into());
+        TestPath(segments)
+    }
+}
+
+impl fmt::Display for TestPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.join("::"))
+    }
+}
+
+/// Build a suite from its pots.
+pub fn suite(name: &str, pots: Vec<Pot>) -> Suite {
+    Suite {
+        name: name.to_string(),
+        pots,
+    }
+}
+
+/// Build a pot from its config and test set.
+pub fn pot(name: &str, config: PotConfig, testset: TestSet) -> Pot {
+    Pot {
+        name: name.to_string(),
+        execution_mode: ExecutionMode::Run,
+        config,
+        testset,
+    }
+}
+
+/// Build a single test. Defaults to [`ExecutionMode::Run`]; the driver adjusts
+/// the mode once the filters are applied.
+pub fn t<F>(name: &str, f: F) -> Test
+where
+    F: Fn(DriverContext) + Send + Sync + 'static,
+{
+    Test {
+        name: name.to_string(),
+        execution_mode: ExecutionMode::Run,
+        f: Box::new(f),
+    }
+}
+
+/// Schedule the given tests in parallel.
+pub fn par(tests: Vec<Test>) -> TestSet {
+    TestSet::Parallel(tests)
+}
+
+/// Schedule the given tests as an ordered sequence.
+pub fn seq(tests: Vec<Test>) -> TestSet {
+    TestSet::Sequence(tests)
+}
+
+/// Schedule a single test as a fuzzing target. The test is stored in the
+/// one-element `Vec` the [`TestSet::Fuzz`] variant holds, so filtering,
+/// shuffling, and layout collection treat it like any other test set.
+pub fn fuzz(test: Test, config: FuzzConfig) -> TestSet {
+    TestSet::Fuzz(vec![test], config)
+}
@@ -1,7 +1,8 @@
-use ic_fondue::prod_tests::cli::CliArgs;
+use ic_fondue::prod_tests::cli::{CliArgs, ResultCompression};
 use ic_fondue::prod_tests::driver_setup::create_driver_context_from_cli;
-use ic_fondue::prod_tests::evaluation::evaluate;
+use ic_fondue::prod_tests::evaluation::{evaluate, TestLifecycle, TestLifecycleEvent};
 use ic_fondue::prod_tests::pot_dsl::*;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use ic_tests::nns_fault_tolerance_test;
 use ic_tests::nns_follow_test::{self, test as follow_test};
 use ic_tests::nns_voting_test::{self, test as voting_test};
@@ -16,8 +17,20 @@ use ic_tests::{
     cycles_minting_test, feature_flags, nns_canister_upgrade_test, registry_authentication_test,
     ssh_access_to_nodes, subnet_creation, transaction_ledger_correctness_test, wasm_generator_test,
 };
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
 use regex::Regex;
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
 
 use structopt::StructOpt;
 
@@ -31,25 +44,113 @@ fn main() -> anyhow::Result<()> {
             .create(true)
             .write(true)
             .open(p)?;
-        writer = Some(std::io::BufWriter::new(Box::new(f)));
+        writer = Some(ResultWriter::new(
+            BufWriter::new(f),
+            validated_args.result_compression,
+        )?);
     }
 
     let mut suite = match get_test_suites().remove(&validated_args.suite) {
         Some(s) => s,
         None => anyhow::bail!(format!("Test suite {} is undefined", &validated_args.suite)),
     };
+    // `--resume-from` loads a prior result document so previously-passing tests
+    // can be skipped (marked `Ignore`) and their outcomes carried over into the
+    // merged output. `--force` disables the skipping and runs everything again.
+    let (resume_doc, resume_passed) = match (&validated_args.resume_from, validated_args.force) {
+        (Some(path), false) => {
+            let doc: Value = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+            let mut passed = HashSet::new();
+            collect_succeeded(&doc, "", &mut passed);
+            (Some(doc), passed)
+        }
+        _ => (None, HashSet::new()),
+    };
+
     apply_filters(
         &mut suite,
         &validated_args.include_pattern,
         &validated_args.ignore_pattern,
         &validated_args.skip_pattern,
+        &resume_passed,
+    );
+
+    // Fold any per-run `--fuzz-iterations` / `--fuzz-corpus-dir` overrides into
+    // the fuzz pots' configs, keeping the in-suite defaults for anything the
+    // caller left unset.
+    apply_fuzz_overrides(
+        &mut suite,
+        validated_args.fuzz_iterations,
+        validated_args.fuzz_corpus_dir.clone(),
     );
 
+    // When a shuffle seed is supplied, deterministically reorder the qualified
+    // tests (and, with `--shuffle-pots`, the pots) so ordering-dependent
+    // flakiness surfaces; the seed
+    // is echoed into the result below so a failing run can be reproduced
+    // bit-for-bit. Sequences stay in declaration order unless explicitly
+    // opted in, since some (like the ssh key-update chain) are ordered by design.
+    let shuffle_seed = validated_args.shuffle_seed;
+    if let Some(seed) = shuffle_seed {
+        shuffle_suite(
+            &mut suite,
+            seed,
+            validated_args.shuffle_sequences,
+            validated_args.shuffle_pots,
+        );
+    }
+
+    let progress = validated_args.progress;
+    // Snapshot the pot/test layout before the suite is consumed by `evaluate`
+    // so the reporter can pre-build a bar for every test that will run.
+    let suite_layout = collect_suite_layout(&suite);
     let context = create_driver_context_from_cli(validated_args, get_hostname());
-    let result = evaluate(&context, suite);
+
+    // Install a signal handler before evaluation starts: a Ctrl-C (SIGINT) or a
+    // SIGTERM flips this flag, which `evaluate` checks between tests and pots.
+    // On cancellation it stops scheduling new pots, tears the driver context
+    // down cleanly, marks the tests that did not run to completion as
+    // interrupted (a non-fuzz test already running is left to finish), and returns the
+    // partial result so the JSON below is still written before we exit non-zero.
+    // `ctrlc` only traps SIGTERM when built with its `termination` feature, so
+    // `ic_tests` enables `ctrlc = { ..., features = ["termination"] }`.
+    let stop_signal = Arc::new(AtomicBool::new(false));
+    {
+        let stop_signal = stop_signal.clone();
+        ctrlc::set_handler(move || {
+            stop_signal.store(true, Ordering::SeqCst);
+        })
+        .expect("Error setting signal handler");
+    }
+
+    // When `--progress` is set, spin up a reporter that renders one indicatif
+    // spinner per test (labelled `pot/test`, all in a single `MultiProgress`
+    // rather than a separate group per pot) and drives the bars off the lifecycle events that
+    // `evaluate` emits on the channel. Without the flag we pass `None` so the
+    // evaluation stays silent and the final JSON is the only output.
+    let mut result = if progress {
+        let (tx, rx) = mpsc::channel();
+        let reporter = thread::spawn(move || report_progress(&suite_layout, rx));
+        let result = evaluate(&context, suite, Some(tx), stop_signal.clone());
+        let _ = reporter.join();
+        result
+    } else {
+        evaluate(&context, suite, None, stop_signal.clone())
+    };
+    result.shuffle_seed = shuffle_seed;
 
     if let Some(mut w) = writer {
-        serde_json::to_writer_pretty(&mut w, &result)?;
+        // On resume, graft the carried-over passing nodes from the prior
+        // document into this run's result so the emitted JSON is a complete
+        // picture of the suite, not just the re-run subset.
+        let mut output = serde_json::to_value(&result)?;
+        if let Some(ref previous) = resume_doc {
+            merge_previous(&mut output, previous, &resume_passed);
+        }
+        serde_json::to_writer_pretty(w.as_write(), &output)?;
+        // Finalize before the failure check below so the `.zst` stream is
+        // always complete, including on the `bail!` path when the suite failed.
+        w.finish()?;
     }
 
     if !result.succeeded {
@@ -63,23 +164,363 @@ fn get_hostname() -> Option<String> {
     std::env::var("HOSTNAME").ok()
 }
 
+/// Output sink for the result JSON. When `zstd` is selected the buffered file
+/// is wrapped in a single-stream `zstd` encoder, mirroring the Base64+zstd
+/// account-encoding path; the stream must be `finish`ed before the process
+/// exits or the `.zst` file is left truncated.
+enum ResultWriter {
+    Plain(BufWriter<File>),
+    Zstd(zstd::stream::write::Encoder<'static, BufWriter<File>>),
+}
+
+impl ResultWriter {
+    fn new(w: BufWriter<File>, compression: ResultCompression) -> anyhow::Result<Self> {
+        Ok(match compression {
+            ResultCompression::None => ResultWriter::Plain(w),
+            // Level 0 lets zstd pick its built-in default, a sane middle
+            // ground for the large, verbose logs suites like
+            // `ssh_access_to_nodes_pot` produce.
+            ResultCompression::Zstd => {
+                ResultWriter::Zstd(zstd::stream::write::Encoder::new(w, 0)?)
+            }
+        })
+    }
+
+    fn as_write(&mut self) -> &mut dyn Write {
+        match self {
+            ResultWriter::Plain(w) => w,
+            ResultWriter::Zstd(w) => w,
+        }
+    }
+
+    /// Flush and finalize the stream. For `zstd` this writes the frame
+    /// epilogue that `serde_json` does not know about.
+    fn finish(self) -> anyhow::Result<()> {
+        match self {
+            ResultWriter::Plain(mut w) => w.flush()?,
+            ResultWriter::Zstd(w) => w.finish()?.flush()?,
+        }
+        Ok(())
+    }
+}
+
+/// The pots (and their qualified tests) that the reporter needs to render,
+/// in the suite's current order (after any shuffling). Only tests in
+/// `ExecutionMode::Run` get a bar.
+fn collect_suite_layout(suite: &Suite) -> Vec<(String, Vec<String>)> {
+    suite
+        .pots
+        .iter()
+        .filter(|p| p.execution_mode == ExecutionMode::Run)
+        .map(|p| {
+            let tests = match &p.testset {
+                TestSet::Parallel(tests) => tests,
+                TestSet::Sequence(tests) => tests,
+                TestSet::Fuzz(tests, _) => tests,
+            };
+            let names = tests
+                .iter()
+                .filter(|t| t.execution_mode == ExecutionMode::Run)
+                .map(|t| t.name.clone())
+                .collect();
+            (p.name.clone(), names)
+        })
+        .collect()
+}
+
+/// Consume the lifecycle events emitted by `evaluate` and drive one spinner
+/// per test (labelled `pot/test`) plus an aggregate bar, all in one
+/// `MultiProgress`; pots get no group of their own. Returns once the sender is dropped,
+/// i.e. when evaluation is done.
+fn report_progress(layout: &[(String, Vec<String>)], rx: mpsc::Receiver<TestLifecycleEvent>) {
+    let mp = MultiProgress::new();
+    let spinner_style = ProgressStyle::default_spinner()
+        .template("  {spinner} {prefix:.bold} {wide_msg}")
+        .unwrap();
+    let bar_style = ProgressStyle::default_bar()
+        .template("{prefix:.bold.dim} [{bar:40}] {pos}/{len} ({elapsed})")
+        .unwrap()
+        .progress_chars("=> ");
+
+    let total: u64 = layout.iter().map(|(_, ts)| ts.len() as u64).sum();
+    let aggregate = mp.add(ProgressBar::new(total));
+    aggregate.set_style(bar_style);
+    aggregate.set_prefix("total");
+
+    // One spinner per test, keyed by (pot, test) so events can find their bar.
+    let mut bars: HashMap<(String, String), ProgressBar> = HashMap::new();
+    for (pot, tests) in layout {
+        for test in tests {
+            let pb = mp.add(ProgressBar::new_spinner());
+            pb.set_style(spinner_style.clone());
+            pb.set_prefix(format!("{}/{}", pot, test));
+            pb.set_message("queued");
+            bars.insert((pot.clone(), test.clone()), pb);
+        }
+    }
+
+    // indicatif only runs its draw loop from inside `MultiProgress::join`, so
+    // the bars never render unless something calls it. Drive it on a dedicated
+    // thread while the event loop below updates the bar handles; `join` returns
+    // once every bar is finished, which happens when `aggregate` is finished.
+    let drawer = thread::spawn(move || {
+        let _ = mp.join_and_clear();
+    });
+
+    let started = Instant::now();
+    for event in rx {
+        if let Some(pb) = bars.get(&(event.pot.clone(), event.test.clone())) {
+            match event.lifecycle {
+                TestLifecycle::Started => {
+                    pb.enable_steady_tick(std::time::Duration::from_millis(120));
+                    pb.set_message("running");
+                }
+                TestLifecycle::Passed => {
+                    pb.finish_with_message("passed");
+                    aggregate.inc(1);
+                }
+                TestLifecycle::Failed => {
+                    pb.finish_with_message("failed");
+                    aggregate.inc(1);
+                }
+            }
+        }
+    }
+    // The channel closed. On an interrupt, in-flight/remaining tests are marked
+    // interrupted without a terminal Passed/Failed event, so their spinners
+    // would never finish and `mp.join_and_clear()` below would block forever.
+    // Finish any still-running bars so the drawer thread returns.
+    for pb in bars.values() {
+        if !pb.is_finished() {
+            pb.finish_with_message("interrupted");
+        }
+    }
+    aggregate.finish_with_message(format!("done in {:?}", started.elapsed()));
+    let _ = drawer.join();
+}
+
+/// Deterministically shuffle the tests within each pot with a seeded RNG.
+/// `Sequence` pots are only shuffled when `shuffle_sequences` is set, since
+/// their order is sometimes meaningful, and the pots themselves are only
+/// reordered when `shuffle_pots` is set.
+fn shuffle_suite(suite: &mut Suite, seed: u64, shuffle_sequences: bool, shuffle_pots: bool) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    for p in suite.pots.iter_mut() {
+        match &mut p.testset {
+            TestSet::Parallel(tests) => tests.shuffle(&mut rng),
+            TestSet::Sequence(tests) => {
+                if shuffle_sequences {
+                    tests.shuffle(&mut rng);
+                }
+            }
+            TestSet::Fuzz(tests, _) => tests.shuffle(&mut rng),
+        }
+    }
+    if shuffle_pots {
+        suite.pots.shuffle(&mut rng);
+    }
+}
+
+/// Apply per-run `--fuzz-iterations` / `--fuzz-corpus-dir` overrides onto every
+/// fuzz pot's `FuzzConfig`, leaving the in-suite defaults in place for the
+/// options the caller did not set.
+fn apply_fuzz_overrides(
+    suite: &mut Suite,
+    iterations: Option<usize>,
+    corpus_dir: Option<PathBuf>,
+) {
+    for p in suite.pots.iter_mut() {
+        if let TestSet::Fuzz(_, config) = &mut p.testset {
+            if let Some(n) = iterations {
+                config.iterations = n;
+            }
+            if let Some(ref dir) = corpus_dir {
+                config.corpus_dir = dir.clone();
+            }
+        }
+    }
+}
+
+/// Walk a prior result document and collect the `suite/pot/test` paths of every
+/// leaf test that `succeeded`, so a resumed run can skip re-running them.
+///
+/// Only leaf (test) nodes are collected: carrying over a pot or the suite root
+/// would replace a whole subtree with the prior document and discard this run's
+/// fresh outcomes for the tests underneath it.
+fn collect_succeeded(value: &Value, prefix: &str, out: &mut HashSet<String>) {
+    match value {
+        Value::Object(obj) => {
+            let path = node_path(obj, prefix);
+            if is_leaf(obj) && obj.get("succeeded").and_then(Value::as_bool) == Some(true) {
+                out.insert(path.clone());
+            }
+            for v in obj.values() {
+                collect_succeeded(v, &path, out);
+            }
+        }
+        Value::Array(arr) => {
+            for v in arr {
+                collect_succeeded(v, prefix, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A result node is a leaf (an individual test) when it has no non-empty
+/// `children` array of sub-results.
+fn is_leaf(obj: &serde_json::Map<String, Value>) -> bool {
+    match obj.get("children").and_then(Value::as_array) {
+        Some(children) => children.is_empty(),
+        None => true,
+    }
+}
+
+/// The `suite/pot/test` path of a result node, extending `prefix` by the node's
+/// `name` field when it carries one.
+fn node_path(obj: &serde_json::Map<String, Value>, prefix: &str) -> String {
+    match obj.get("name").and_then(Value::as_str) {
+        Some(name) if prefix.is_empty() => name.to_string(),
+        Some(name) => format!("{}/{}", prefix, name),
+        _ => prefix.to_string(),
+    }
+}
+
+/// Collect every result node of a document keyed by its `suite/pot/test` path,
+/// so carried-over nodes can be looked up and re-inserted.
+fn collect_nodes(value: &Value, prefix: &str, out: &mut HashMap<String, Value>) {
+    match value {
+        Value::Object(obj) => {
+            let path = node_path(obj, prefix);
+            if obj.contains_key("succeeded") {
+                out.insert(path.clone(), value.clone());
+            }
+            for v in obj.values() {
+                collect_nodes(v, &path, out);
+            }
+        }
+        Value::Array(arr) => {
+            for v in arr {
+                collect_nodes(v, prefix, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The mutable `children` array of a result node, created empty if absent.
+fn children_mut(node: &mut Value) -> &mut Vec<Value> {
+    let obj = node.as_object_mut().expect("result node is an object");
+    obj.entry("children")
+        .or_insert_with(|| Value::Array(Vec::new()));
+    obj.get_mut("children")
+        .and_then(Value::as_array_mut)
+        .expect("children is an array")
+}
+
+/// Find the child of `children` with the given `name`.
+fn child_by_name<'a>(children: &'a mut [Value], name: &str) -> Option<&'a mut Value> {
+    children
+        .iter_mut()
+        .find(|c| c.get("name").and_then(Value::as_str) == Some(name))
+}
+
+/// Merge the carried-over passing tests from `previous` into `current` so the
+/// emitted document is a complete picture of the suite: previously-passing
+/// tests were `Ignore`d this run and are therefore absent from `current`, so we
+/// *insert* them (adding their pot node when the whole pot was skipped) rather
+/// than only replacing leaves that happen to still be present. Success flags
+/// are then recomputed bottom-up.
+fn merge_previous(current: &mut Value, previous: &Value, passed: &HashSet<String>) {
+    let mut prev_nodes = HashMap::new();
+    collect_nodes(previous, "", &mut prev_nodes);
+
+    for path in passed {
+        let parts: Vec<&str> = path.split('/').collect();
+        // `passed` holds only leaf (suite/pot/test) paths.
+        if parts.len() != 3 {
+            continue;
+        }
+        let (pot_name, test_name) = (parts[1], parts[2]);
+
+        // Ensure the pot node exists, inserting a shell from the prior document
+        // when this run skipped the whole pot.
+        if child_by_name(children_mut(current), pot_name).is_none() {
+            let pot_path = format!("{}/{}", parts[0], pot_name);
+            if let Some(prev_pot) = prev_nodes.get(&pot_path) {
+                let mut shell = prev_pot.clone();
+                if let Some(obj) = shell.as_object_mut() {
+                    obj.insert("children".to_string(), Value::Array(Vec::new()));
+                }
+                children_mut(current).push(shell);
+            } else {
+                continue;
+            }
+        }
+
+        // Ensure the carried-over test exists under that pot.
+        let pot_children = children_mut(current);
+        let pot_node = child_by_name(pot_children, pot_name).expect("pot just ensured");
+        if child_by_name(children_mut(pot_node), test_name).is_none() {
+            if let Some(prev_test) = prev_nodes.get(path) {
+                children_mut(pot_node).push(prev_test.clone());
+            }
+        }
+    }
+
+    recompute_succeeded(current);
+}
+
+/// Recompute each inner node's `succeeded` as the conjunction of its children,
+/// after carried-over nodes have been merged in. Leaf nodes keep their own flag.
+fn recompute_succeeded(node: &mut Value) -> bool {
+    let obj = match node.as_object_mut() {
+        Some(obj) => obj,
+        None => return true,
+    };
+    let mut has_children = false;
+    let mut all = true;
+    if let Some(Value::Array(children)) = obj.get_mut("children") {
+        if !children.is_empty() {
+            has_children = true;
+            for child in children.iter_mut() {
+                all = recompute_succeeded(child) && all;
+            }
+        }
+    }
+    if has_children {
+        obj.insert("succeeded".to_string(), Value::Bool(all));
+        all
+    } else {
+        obj.get("succeeded").and_then(Value::as_bool).unwrap_or(false)
+    }
+}
+
 fn apply_filters(
     suite: &mut Suite,
     include: &Option<Regex>,
     ignore: &Option<Regex>,
     skip: &Option<Regex>,
+    resume_passed: &HashSet<String>,
 ) {
+    let suite_name = suite.name.clone();
     for p in suite.pots.iter_mut() {
         let tests = match &mut p.testset {
             TestSet::Parallel(tests) => tests,
             TestSet::Sequence(tests) => tests,
+            TestSet::Fuzz(tests, _) => tests,
         };
         for t in tests.iter_mut() {
             let path = TestPath::new()
-                .join(suite.name.clone())
+                .join(suite_name.clone())
                 .join(p.name.clone())
                 .join(t.name.clone());
             t.execution_mode = resolve_execution_mode(&format!("{}", path), include, ignore, skip);
+            // A test that succeeded in the resumed-from run is skipped so its
+            // pot is only provisioned for tests that still need to run.
+            if resume_passed.contains(&format!("{}/{}/{}", suite_name, p.name, t.name)) {
+                t.execution_mode = ExecutionMode::Ignore;
+            }
         }
         // At least one test is qualified for running. A corresponding pot needs to be
         // set up.
@@ -178,10 +619,17 @@ fn get_test_suites() -> HashMap<String, Suite> {
                 pot(
                     "nns_voting_fuzzing_poc_pot",
                     nns_voting_fuzzing_poc_test::config(),
-                    par(vec![t(
-                        "nns_voting_fuzzing_poc_test",
-                        nns_voting_fuzzing_poc_test::test,
-                    )]),
+                    fuzz(
+                        t(
+                            "nns_voting_fuzzing_poc_test",
+                            nns_voting_fuzzing_poc_test::test,
+                        ),
+                        FuzzConfig {
+                            generator: nns_voting_fuzzing_poc_test::fuzz_input,
+                            iterations: 1024,
+                            corpus_dir: PathBuf::from("fuzz_corpus/nns_voting_fuzzing_poc"),
+                        },
+                    ),
                 ),
                 pot(
                     "nns_canister_upgrade_pot",
@@ -0,0 +1,38 @@
+//! Proof-of-concept fuzzing target for NNS voting.
+//!
+//! `fuzz_input` is the input generator the fuzz execution mode uses, and
+//! `test` is the target it feeds. The single-subnet setup and the decoding of
+//! the input against the voting endpoints are elided in this file: `config`
+//! provisions nothing and `test` reads the input without using it.
+
+use ic_fondue::prod_tests::driver_setup::DriverContext;
+use ic_fondue::prod_tests::evaluation::fuzz_input as current_fuzz_input;
+use ic_fondue::prod_tests::pot_dsl::PotConfig;
+use rand::rngs::StdRng;
+use rand::RngCore;
+
+/// The pot config for the fuzz target. The topology setup is elided, so the
+/// closure does nothing.
+pub fn config() -> PotConfig {
+    PotConfig(Box::new(|_ctx: DriverContext| {
+        // Single-subnet setup; elided here.
+    }))
+}
+
+/// Generate a fuzz input: a fresh random payload of 0 to 255 bytes. The RNG is
+/// seeded by the driver so runs reproduce.
+pub fn fuzz_input(rng: &mut StdRng) -> Vec<u8> {
+    let len = (rng.next_u32() % 256) as usize;
+    let mut buf = vec![0u8; len];
+    rng.fill_bytes(&mut buf);
+    buf
+}
+
+/// Read the current fuzz input. Decoding it and driving the NNS voting path
+/// are elided, so this target never fails.
+pub fn test(_ctx: DriverContext) {
+    let input = current_fuzz_input();
+    // Drive the voting endpoints with the payload the fuzz driver prepared;
+    // decoding/assertions elided.
+    let _ = input;
+}